@@ -16,6 +16,7 @@ mod ops;
 mod prev;
 mod reword;
 mod run;
+mod run_cache;
 mod stack;
 mod sync;
 
@@ -21,6 +21,26 @@ pub struct RunArgs {
     /// Don't actually switch
     #[arg(short = 'n', long)]
     dry_run: bool,
+
+    /// Binary-search the stack for the first failing commit, assuming failure is monotonic
+    #[arg(long, conflicts_with = "no_fail_fast")]
+    bisect: bool,
+
+    /// Run across this many linked worktrees in parallel
+    #[arg(short = 'j', long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..), conflicts_with = "bisect")]
+    jobs: u16,
+
+    /// Fold changes the command makes back into the commit they came from
+    #[arg(long, alias = "fixup", conflicts_with_all = ["bisect", "jobs"])]
+    amend: bool,
+
+    /// Don't read or write the run-result cache
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Ignore cached results for this run, but still repopulate the cache
+    #[arg(long, conflicts_with = "no_cache")]
+    refresh: bool,
 }
 
 impl RunArgs {
@@ -76,7 +96,7 @@ impl RunArgs {
         }
 
         let head_branch = repo.head_branch();
-        let head_id = repo.head_commit().id;
+        let mut head_id = repo.head_commit().id;
         let base = crate::ops::resolve_implicit_base(
             &repo,
             head_id,
@@ -97,74 +117,84 @@ impl RunArgs {
         let graph = git_stack::graph::Graph::from_branches(&repo, stack_branches)
             .with_code(proc_exit::Code::FAILURE)?;
 
-        let mut first_failure = None;
+        let mut run_cache = if self.no_cache {
+            None
+        } else {
+            Some(crate::run_cache::RunCache::load(repo.raw().path()))
+        };
 
+        let mut first_failure = None;
         let mut success = true;
-        let mut cursor = graph.descendants_of(merge_base_oid).into_cursor();
-        while let Some(current_id) = cursor.next(&graph) {
-            let current_commit = repo
-                .find_commit(current_id)
-                .expect("children/head are always present");
-            let _ = writeln!(
-                std::io::stderr(),
-                "{} to {}: {}",
-                stderr_palette.good.paint("Switching"),
-                stderr_palette
-                    .highlight
-                    .paint(crate::ops::render_id(&repo, &branches, current_id)),
-                stderr_palette.hint.paint(&current_commit.summary)
-            );
-            if !self.dry_run {
-                repo.switch_commit(current_id)
-                    .with_code(proc_exit::Code::FAILURE)?;
+
+        if self.bisect {
+            let mut ids = Vec::new();
+            let mut cursor = graph.descendants_of(merge_base_oid).into_cursor();
+            while let Some(current_id) = cursor.next(&graph) {
+                ids.push(current_id);
             }
-            let status = std::process::Command::new(&self.command[0])
-                .args(&self.command[1..])
-                .status();
-            let mut current_success = true;
-            match status {
-                Ok(status) if status.success() => {
-                    let _ = writeln!(
-                        std::io::stderr(),
-                        "{}",
-                        stderr_palette.good.paint("Success"),
-                    );
+
+            let mut switch_err = None;
+            let first_failure_index = bisect_first_failure(ids.len(), |mid| {
+                let current_id = ids[mid];
+                if let Err(err) = self.switch_to(&mut repo, &branches, current_id, &stderr_palette) {
+                    // The search needs a bool; stash the real error and stop trusting the
+                    // result the moment we can no longer check out a commit to test.
+                    switch_err.get_or_insert(err);
+                    return true;
                 }
-                Ok(status) => match status.code() {
-                    Some(code) => {
-                        let _ = writeln!(
-                            std::io::stderr(),
-                            "{}: exit code {}",
-                            stderr_palette.error.paint("Failed"),
-                            code,
-                        );
-                        current_success = false;
-                    }
-                    None => {
-                        let _ = writeln!(
-                            std::io::stderr(),
-                            "{}: signal caught",
-                            stderr_palette.error.paint("Failed"),
-                        );
-                        current_success = false;
+                self.run_cached(&repo, current_id, &mut run_cache, &stderr_palette)
+            });
+            if let Some(err) = switch_err {
+                return Err(err);
+            }
+
+            if first_failure_index < ids.len() {
+                first_failure = Some(ids[first_failure_index]);
+                success = false;
+            }
+        } else if self.jobs > 1 {
+            let mut ids = Vec::new();
+            let mut cursor = graph.descendants_of(merge_base_oid).into_cursor();
+            while let Some(current_id) = cursor.next(&graph) {
+                ids.push(current_id);
+            }
+
+            let (parallel_success, parallel_first_failure) =
+                self.run_parallel(&repo, &ids, &mut run_cache, &stderr_palette)?;
+            success = parallel_success;
+            first_failure = parallel_first_failure;
+        } else if self.amend {
+            let (amend_success, amend_first_failure, amend_tip) = self.run_amend(
+                &mut repo,
+                &branches,
+                merge_base_oid,
+                head_id,
+                &mut run_cache,
+                &stderr_palette,
+            )?;
+            success = amend_success;
+            first_failure = amend_first_failure;
+            // Amending rewrites commit ids, including the tip's if it was the one
+            // reformatted; use the tip `run_amend` tracked through those rewrites rather
+            // than the stale pre-amend `head_id`.
+            head_id = amend_tip;
+        } else {
+            let mut cursor = graph.descendants_of(merge_base_oid).into_cursor();
+            while let Some(current_id) = cursor.next(&graph) {
+                self.switch_to(&mut repo, &branches, current_id, &stderr_palette)?;
+                if !self.run_cached(&repo, current_id, &mut run_cache, &stderr_palette) {
+                    first_failure.get_or_insert(current_id);
+                    if self.fail_fast() {
+                        cursor.stop();
                     }
-                },
-                Err(err) => {
-                    let _ = writeln!(
-                        std::io::stderr(),
-                        "{}: {}",
-                        stderr_palette.error.paint("Failed"),
-                        err
-                    );
-                    current_success = false;
+                    success = false;
                 }
             }
-            if !current_success {
-                first_failure.get_or_insert(current_id);
-                if self.fail_fast() {
-                    cursor.stop();
-                }
-                success = false;
+        }
+
+        if let Some(run_cache) = run_cache.as_ref() {
+            if let Err(err) = run_cache.save(repo.raw().path()) {
+                log::warn!("failed to save run-result cache: {err}");
             }
         }
 
@@ -207,6 +237,421 @@ impl RunArgs {
     fn fail_fast(&self) -> bool {
         resolve_bool_arg(self.fail_fast, self.no_fail_fast).unwrap_or(true)
     }
+
+    fn switch_to(
+        &self,
+        repo: &mut git_stack::git::GitRepo,
+        branches: &git_stack::graph::BranchSet,
+        commit_id: git2::Oid,
+        stderr_palette: &crate::ops::Palette,
+    ) -> proc_exit::ExitResult {
+        let commit = repo
+            .find_commit(commit_id)
+            .expect("children/head are always present");
+        let _ = writeln!(
+            std::io::stderr(),
+            "{} to {}: {}",
+            stderr_palette.good.paint("Switching"),
+            stderr_palette
+                .highlight
+                .paint(crate::ops::render_id(repo, branches, commit_id)),
+            stderr_palette.hint.paint(&commit.summary)
+        );
+        if !self.dry_run {
+            repo.switch_commit(commit_id)
+                .with_code(proc_exit::Code::FAILURE)?;
+        }
+        Ok(())
+    }
+
+    fn run_once(&self, stderr_palette: &crate::ops::Palette) -> bool {
+        let status = std::process::Command::new(&self.command[0])
+            .args(&self.command[1..])
+            .status();
+        match status {
+            Ok(status) if status.success() => {
+                let _ = writeln!(
+                    std::io::stderr(),
+                    "{}",
+                    stderr_palette.good.paint("Success"),
+                );
+                true
+            }
+            Ok(status) => match status.code() {
+                Some(code) => {
+                    let _ = writeln!(
+                        std::io::stderr(),
+                        "{}: exit code {}",
+                        stderr_palette.error.paint("Failed"),
+                        code,
+                    );
+                    false
+                }
+                None => {
+                    let _ = writeln!(
+                        std::io::stderr(),
+                        "{}: signal caught",
+                        stderr_palette.error.paint("Failed"),
+                    );
+                    false
+                }
+            },
+            Err(err) => {
+                let _ = writeln!(
+                    std::io::stderr(),
+                    "{}: {}",
+                    stderr_palette.error.paint("Failed"),
+                    err
+                );
+                false
+            }
+        }
+    }
+
+    /// Run the command against `commit_id`, skipping it if a prior run already recorded a
+    /// success for this exact command against the commit's tree.
+    fn run_cached(
+        &self,
+        repo: &git_stack::git::GitRepo,
+        commit_id: git2::Oid,
+        cache: &mut Option<crate::run_cache::RunCache>,
+        stderr_palette: &crate::ops::Palette,
+    ) -> bool {
+        let tree_id = cache.is_some().then(|| {
+            repo.find_commit(commit_id)
+                .expect("children/head are always present")
+                .tree_id
+        });
+
+        if !self.refresh {
+            if let (Some(cache), Some(tree_id)) = (cache.as_ref(), tree_id) {
+                if cache.hit(&self.command, tree_id) {
+                    let _ = writeln!(
+                        std::io::stderr(),
+                        "{}",
+                        stderr_palette.good.paint("Cached: success"),
+                    );
+                    return true;
+                }
+            }
+        }
+
+        let success = self.run_once(stderr_palette);
+
+        if success {
+            if let (Some(cache), Some(tree_id)) = (cache.as_mut(), tree_id) {
+                cache.record_success(&self.command, tree_id, now_unix());
+            }
+        }
+
+        success
+    }
+
+    /// Run the command across `ids` concurrently, each in its own linked worktree.
+    ///
+    /// The primary working tree is never touched; callers are responsible for switching it
+    /// afterward (e.g. to the first failure) once this has drained.
+    fn run_parallel(
+        &self,
+        repo: &git_stack::git::GitRepo,
+        ids: &[git2::Oid],
+        cache: &mut Option<crate::run_cache::RunCache>,
+        stderr_palette: &crate::ops::Palette,
+    ) -> proc_exit::Result<(bool, Option<git2::Oid>)> {
+        let repo_path = repo.raw().path().to_owned();
+        let temp_root =
+            std::env::temp_dir().join(format!("git-stack-run-{}", std::process::id()));
+        std::fs::create_dir_all(&temp_root).with_code(proc_exit::Code::FAILURE)?;
+        let _cleanup = TempDirGuard(temp_root.clone());
+
+        let jobs = usize::from(self.jobs);
+        let mut success = true;
+        let mut first_failure = None;
+        'chunks: for chunk in ids.chunks(jobs) {
+            // Look up each commit's tree before spawning anything so a cache hit skips the
+            // worktree checkout entirely instead of just skipping the command inside it.
+            let mut to_run = Vec::new();
+            for &commit_id in chunk {
+                let tree_id = cache.is_some().then(|| {
+                    repo.find_commit(commit_id)
+                        .expect("children/head are always present")
+                        .tree_id
+                });
+                let cached = !self.refresh
+                    && cache
+                        .as_ref()
+                        .zip(tree_id)
+                        .is_some_and(|(cache, tree_id)| cache.hit(&self.command, tree_id));
+                if cached {
+                    let _ = writeln!(
+                        std::io::stderr(),
+                        "{}: {}",
+                        stderr_palette.highlight.paint(commit_id.to_string()),
+                        stderr_palette.good.paint("Cached: success"),
+                    );
+                } else {
+                    to_run.push((commit_id, tree_id));
+                }
+            }
+
+            let results: Vec<_> = std::thread::scope(|scope| {
+                to_run
+                    .iter()
+                    .enumerate()
+                    .map(|(slot, &(commit_id, _))| {
+                        let repo_path = &repo_path;
+                        let temp_root = &temp_root;
+                        let command = &self.command;
+                        let handle = scope
+                            .spawn(move || run_worktree_job(repo_path, temp_root, slot, commit_id, command));
+                        (commit_id, handle)
+                    })
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .map(|(commit_id, handle)| {
+                        handle.join().unwrap_or_else(|_| {
+                            WorktreeJobResult::setup_failure(
+                                commit_id,
+                                "worker thread panicked".to_owned(),
+                            )
+                        })
+                    })
+                    .collect()
+            });
+
+            for (result, &(_, tree_id)) in results.into_iter().zip(to_run.iter()) {
+                let _ = writeln!(
+                    std::io::stderr(),
+                    "{} to {}",
+                    stderr_palette.good.paint("Ran"),
+                    stderr_palette.highlight.paint(result.commit_id.to_string()),
+                );
+                if !result.output.is_empty() {
+                    let _ = std::io::stderr().write_all(&result.output);
+                }
+                if result.success {
+                    let _ = writeln!(std::io::stderr(), "{}", stderr_palette.good.paint("Success"));
+                    if let (Some(cache), Some(tree_id)) = (cache.as_mut(), tree_id) {
+                        cache.record_success(&self.command, tree_id, now_unix());
+                    }
+                } else {
+                    let _ = writeln!(
+                        std::io::stderr(),
+                        "{}: {}",
+                        stderr_palette.error.paint("Failed"),
+                        result.message
+                    );
+                    first_failure.get_or_insert(result.commit_id);
+                    success = false;
+                }
+            }
+
+            if !success && self.fail_fast() {
+                break 'chunks;
+            }
+        }
+
+        Ok((success, first_failure))
+    }
+
+    /// Run the command across the stack, amending any changes it produces back into the
+    /// commit that produced them and rebasing the remaining descendants onto the result.
+    ///
+    /// Returns the (possibly rewritten) tip of the stack alongside the usual success/failure
+    /// bookkeeping. The tip is threaded through explicitly rather than re-read from
+    /// `repo.head_commit()`, since nothing guarantees `rebase_descendants` leaves HEAD parked
+    /// on the true tip of the rewritten stack.
+    fn run_amend(
+        &self,
+        repo: &mut git_stack::git::GitRepo,
+        branches: &git_stack::graph::BranchSet,
+        merge_base_oid: git2::Oid,
+        head_id: git2::Oid,
+        cache: &mut Option<crate::run_cache::RunCache>,
+        stderr_palette: &crate::ops::Palette,
+    ) -> proc_exit::Result<(bool, Option<git2::Oid>, git2::Oid)> {
+        let mut success = true;
+        let mut first_failure = None;
+        let mut root = merge_base_oid;
+        let mut tip = head_id;
+
+        'walk: loop {
+            let stack_branches = branches.dependents(&*repo, root, tip);
+            let graph = git_stack::graph::Graph::from_branches(&*repo, stack_branches)
+                .with_code(proc_exit::Code::FAILURE)?;
+            let mut cursor = graph.descendants_of(root).into_cursor();
+            while let Some(current_id) = cursor.next(&graph) {
+                self.switch_to(repo, branches, current_id, stderr_palette)?;
+                let current_success = self.run_cached(&*repo, current_id, cache, stderr_palette);
+                if !current_success {
+                    first_failure.get_or_insert(current_id);
+                    if repo.is_dirty() {
+                        // A formatter/codemod can exit non-zero after already rewriting
+                        // files; stash that away rather than letting it silently ride
+                        // along into the next commit's checkout or the final switch back.
+                        if git_stack::git::stash_push(repo, "git-stack run --amend (failed)")
+                            .is_some()
+                        {
+                            let _ = writeln!(
+                                std::io::stderr(),
+                                "{}: command left the working tree dirty; changes were stashed",
+                                stderr_palette.error.paint("warning"),
+                            );
+                        } else {
+                            let _ = writeln!(
+                                std::io::stderr(),
+                                "{}: command left the working tree dirty and it could not be stashed",
+                                stderr_palette.error.paint("warning"),
+                            );
+                        }
+                    }
+                    if self.fail_fast() {
+                        cursor.stop();
+                    }
+                    success = false;
+                    continue;
+                }
+
+                if !repo.is_dirty() {
+                    continue;
+                }
+
+                if self.dry_run {
+                    let _ = writeln!(
+                        std::io::stderr(),
+                        "{} {}:\n{}",
+                        stderr_palette.good.paint("Would amend"),
+                        stderr_palette
+                            .highlight
+                            .paint(crate::ops::render_id(repo, branches, current_id)),
+                        crate::ops::diff_stat(repo).with_code(proc_exit::Code::FAILURE)?,
+                    );
+                    continue;
+                }
+
+                repo.stage_all().with_code(proc_exit::Code::FAILURE)?;
+                let new_id = crate::amend::amend_commit(repo, current_id)
+                    .with_code(proc_exit::Code::FAILURE)?;
+                let _ = writeln!(
+                    std::io::stderr(),
+                    "{} into {}",
+                    stderr_palette.good.paint("Amended"),
+                    stderr_palette
+                        .highlight
+                        .paint(crate::ops::render_id(repo, branches, new_id)),
+                );
+                let rebased_tip = crate::ops::rebase_descendants(repo, branches, current_id, new_id)
+                    .with_code(proc_exit::Code::FAILURE)?;
+
+                root = new_id;
+                tip = if current_id == tip { new_id } else { rebased_tip };
+                continue 'walk;
+            }
+
+            break;
+        }
+
+        Ok((success, first_failure, tip))
+    }
+}
+
+struct TempDirGuard(std::path::PathBuf);
+
+impl Drop for TempDirGuard {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+struct WorktreeJobResult {
+    commit_id: git2::Oid,
+    success: bool,
+    message: String,
+    output: Vec<u8>,
+}
+
+impl WorktreeJobResult {
+    fn setup_failure(commit_id: git2::Oid, message: String) -> Self {
+        Self {
+            commit_id,
+            success: false,
+            message,
+            output: Vec::new(),
+        }
+    }
+}
+
+fn run_worktree_job(
+    repo_path: &std::path::Path,
+    temp_root: &std::path::Path,
+    slot: usize,
+    commit_id: git2::Oid,
+    command: &[std::ffi::OsString],
+) -> WorktreeJobResult {
+    let repo = match git2::Repository::open(repo_path) {
+        Ok(repo) => repo,
+        Err(err) => return WorktreeJobResult::setup_failure(commit_id, err.to_string()),
+    };
+
+    let name = format!("git-stack-run-{slot}-{commit_id}");
+    let worktree_path = temp_root.join(&name);
+    let worktree = match repo.worktree(&name, &worktree_path, None) {
+        Ok(worktree) => worktree,
+        Err(err) => return WorktreeJobResult::setup_failure(commit_id, err.to_string()),
+    };
+    // Registered as soon as `worktree()` succeeds, so every return below (including the
+    // early ones on setup failure) prunes the `.git/worktrees/<name>` admin entry; without
+    // this a failed setup would leave it behind and make the next `--jobs` run for the same
+    // commit fail with "worktree already exists".
+    let _prune_on_drop = WorktreePruneGuard(&worktree);
+
+    let worktree_repo = match git2::Repository::open_from_worktree(&worktree) {
+        Ok(repo) => repo,
+        Err(err) => return WorktreeJobResult::setup_failure(commit_id, err.to_string()),
+    };
+    if let Err(err) = worktree_repo.set_head_detached(commit_id) {
+        return WorktreeJobResult::setup_failure(commit_id, err.to_string());
+    }
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    checkout.force();
+    if let Err(err) = worktree_repo.checkout_head(Some(&mut checkout)) {
+        return WorktreeJobResult::setup_failure(commit_id, err.to_string());
+    }
+
+    let output = std::process::Command::new(&command[0])
+        .args(&command[1..])
+        .current_dir(&worktree_path)
+        .output();
+
+    match output {
+        Ok(output) => WorktreeJobResult {
+            commit_id,
+            success: output.status.success(),
+            message: match output.status.code() {
+                Some(code) => format!("exit code {code}"),
+                None => "signal caught".to_owned(),
+            },
+            output: [output.stdout, output.stderr].concat(),
+        },
+        Err(err) => WorktreeJobResult::setup_failure(commit_id, err.to_string()),
+    }
+}
+
+struct WorktreePruneGuard<'a>(&'a git2::Worktree);
+
+impl Drop for WorktreePruneGuard<'_> {
+    fn drop(&mut self) {
+        let mut prune_opts = git2::WorktreePruneOptions::new();
+        prune_opts.valid(true).working_tree(true);
+        let _ = self.0.prune(Some(&mut prune_opts));
+    }
+}
+
+fn now_unix() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
 }
 
 fn resolve_bool_arg(yes: bool, no: bool) -> Option<bool> {
@@ -217,3 +662,55 @@ fn resolve_bool_arg(yes: bool, no: bool) -> Option<bool> {
         (_, _) => unreachable!("clap should make this impossible"),
     }
 }
+
+/// Binary-search `0..len` under the assumption that `is_success` is `true` for some prefix and
+/// `false` for the rest, returning the index of the first failure (or `len` if none fail).
+fn bisect_first_failure(len: usize, mut is_success: impl FnMut(usize) -> bool) -> usize {
+    let mut lo = 0;
+    let mut hi = len;
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if is_success(mid) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bisect_empty_stack() {
+        assert_eq!(bisect_first_failure(0, |_| true), 0);
+    }
+
+    #[test]
+    fn bisect_all_pass() {
+        assert_eq!(bisect_first_failure(5, |_| true), 5);
+    }
+
+    #[test]
+    fn bisect_all_fail() {
+        assert_eq!(bisect_first_failure(5, |_| false), 0);
+    }
+
+    #[test]
+    fn bisect_single_failure_at_start() {
+        // Under the monotonic assumption, a failure at index 0 persists through the rest.
+        assert_eq!(bisect_first_failure(5, |_| false), 0);
+    }
+
+    #[test]
+    fn bisect_single_failure_in_middle() {
+        assert_eq!(bisect_first_failure(5, |i| i < 2), 2);
+    }
+
+    #[test]
+    fn bisect_single_failure_at_end() {
+        assert_eq!(bisect_first_failure(5, |i| i < 4), 4);
+    }
+}
@@ -0,0 +1,147 @@
+//! Persistent cache of `stack run` results, keyed by the command and the tree OID it was run
+//! against, so unchanged commits can be skipped on repeated invocations.
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+const CACHE_RELATIVE_PATH: &str = "git-stack/run-cache";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RunCache {
+    entries: HashMap<String, CacheEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    exit_code: i32,
+    timestamp: u64,
+}
+
+impl RunCache {
+    /// Load the cache from `.git/git-stack/run-cache`, treating anything unreadable or
+    /// corrupt as an empty cache rather than failing the run.
+    pub fn load(git_dir: &Path) -> Self {
+        std::fs::read(cache_path(git_dir))
+            .ok()
+            .and_then(|raw| serde_json::from_slice(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, git_dir: &Path) -> std::io::Result<()> {
+        let path = cache_path(git_dir);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let raw = serde_json::to_vec_pretty(self).unwrap_or_default();
+        std::fs::write(path, raw)
+    }
+
+    /// Returns `true` if `command` was previously recorded as succeeding against `tree_id`.
+    pub fn hit(&self, command: &[std::ffi::OsString], tree_id: git2::Oid) -> bool {
+        self.entries.contains_key(&cache_key(command, tree_id))
+    }
+
+    /// Only successes are cached: a cached failure would mask a flaky test becoming reliably
+    /// broken, so failures are always re-run.
+    pub fn record_success(
+        &mut self,
+        command: &[std::ffi::OsString],
+        tree_id: git2::Oid,
+        timestamp: u64,
+    ) {
+        self.entries.insert(
+            cache_key(command, tree_id),
+            CacheEntry {
+                exit_code: 0,
+                timestamp,
+            },
+        );
+    }
+}
+
+fn cache_path(git_dir: &Path) -> PathBuf {
+    git_dir.join(CACHE_RELATIVE_PATH)
+}
+
+fn cache_key(command: &[std::ffi::OsString], tree_id: git2::Oid) -> String {
+    format!("{:016x}:{}", command_hash(command), tree_id)
+}
+
+fn command_hash(command: &[std::ffi::OsString]) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    command.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(args: &[&str]) -> Vec<std::ffi::OsString> {
+        args.iter().map(std::ffi::OsString::from).collect()
+    }
+
+    fn oid(byte: u8) -> git2::Oid {
+        git2::Oid::from_bytes(&[byte; 20]).unwrap()
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "git-stack-run-cache-test-{name}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn miss_before_any_record() {
+        let cache = RunCache::default();
+        assert!(!cache.hit(&command(&["cargo", "test"]), oid(1)));
+    }
+
+    #[test]
+    fn hit_after_recording_the_same_tree() {
+        let mut cache = RunCache::default();
+        let cmd = command(&["cargo", "test"]);
+        cache.record_success(&cmd, oid(1), 1_700_000_000);
+        assert!(cache.hit(&cmd, oid(1)));
+    }
+
+    #[test]
+    fn miss_for_a_different_tree_or_command() {
+        let mut cache = RunCache::default();
+        let cmd = command(&["cargo", "test"]);
+        cache.record_success(&cmd, oid(1), 1_700_000_000);
+
+        assert!(!cache.hit(&cmd, oid(2)));
+        assert!(!cache.hit(&command(&["cargo", "fmt"]), oid(1)));
+    }
+
+    #[test]
+    fn round_trips_through_disk() {
+        let git_dir = scratch_dir("round-trip");
+        let cmd = command(&["cargo", "test"]);
+
+        let mut cache = RunCache::load(&git_dir);
+        assert!(!cache.hit(&cmd, oid(1)));
+
+        cache.record_success(&cmd, oid(1), 1_700_000_000);
+        cache.save(&git_dir).expect("save succeeds");
+
+        let reloaded = RunCache::load(&git_dir);
+        assert!(reloaded.hit(&cmd, oid(1)));
+
+        std::fs::remove_dir_all(&git_dir).expect("cleanup succeeds");
+    }
+
+    #[test]
+    fn missing_cache_file_loads_as_empty() {
+        let git_dir = scratch_dir("missing");
+        let cache = RunCache::load(&git_dir);
+        assert!(!cache.hit(&command(&["cargo", "test"]), oid(1)));
+    }
+}